@@ -0,0 +1,99 @@
+//! Batches several [`Kernel`] dispatches (and buffer-to-buffer copies) into a single command
+//! submission, so a multi-stage pipeline doesn't pay one `submit`/GPU-sync per stage.
+
+use crate::{Framework, GpuBuffer, Kernel, ShaderKind};
+
+/// Records a sequence of kernel dispatches and buffer copies, then submits them all at once.
+///
+/// Built via [`Framework::create_batch`]. `Cpu`-mode kernels (see [`ShaderKind`]) run
+/// immediately when added, since there's no GPU command stream to batch them into; `Wgpu`-mode
+/// kernels and copies are recorded into one encoder and only actually run on [`CommandBatch::submit`].
+pub struct CommandBatch<'fw> {
+    fw: &'fw Framework,
+    // `None` until the first GPU-mode kernel or copy is recorded, so an all-CPU batch never
+    // touches `fw.gpu_device()` at all.
+    encoder: Option<wgpu::CommandEncoder>,
+}
+
+impl<'fw> CommandBatch<'fw> {
+    pub(crate) fn new(fw: &'fw Framework) -> Self {
+        Self { fw, encoder: None }
+    }
+
+    fn encoder(&mut self) -> &mut wgpu::CommandEncoder {
+        let fw = self.fw;
+        self.encoder.get_or_insert_with(|| {
+            fw.gpu_device()
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("CommandBatch encoder"),
+                })
+        })
+    }
+
+    /// Records `kernel`'s dispatch with the given workgroup grid, chaining it after anything
+    /// already recorded in this batch.
+    pub fn add_kernel<'res>(&mut self, kernel: &Kernel<'fw, 'res>, x: u32, y: u32, z: u32) -> &mut Self {
+        match kernel.mode {
+            // No encoder to batch into; just run it now.
+            ShaderKind::Cpu => kernel.enqueue(x, y, z),
+            ShaderKind::Wgpu => {
+                let pipeline = kernel.pipeline.as_ref().expect("Kernel has no GPU pipeline");
+                let encoder = self.encoder();
+                let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                    label: Some("CommandBatch compute pass"),
+                });
+
+                compute_pass.set_pipeline(pipeline);
+                for (id, bindgroup) in kernel.bindgroups.iter().enumerate() {
+                    compute_pass.set_bind_group(id as u32, bindgroup, &[]);
+                }
+                if let Some(data) = &kernel.push_constants {
+                    compute_pass.set_push_constants(0, data);
+                }
+                compute_pass.insert_debug_marker(&kernel.entry_point);
+                compute_pass.dispatch_workgroups(x, y, z);
+            }
+        }
+
+        self
+    }
+
+    /// Records a copy from `src` into `dst`, so a kernel's output can feed straight into the
+    /// next kernel's input without a CPU round-trip.
+    pub fn copy<T: bytemuck::Pod>(&mut self, src: &GpuBuffer<'fw, T>, dst: &GpuBuffer<'fw, T>) -> &mut Self {
+        let size = src.size.min(dst.size);
+
+        match (src.as_cpu_backing(), dst.as_cpu_backing()) {
+            (None, None) => {
+                let encoder = self.encoder();
+                encoder.copy_buffer_to_buffer(src.as_gpu_buffer(), 0, dst.as_gpu_buffer(), 0, size);
+            }
+            (Some(src_host), Some(dst_host)) => {
+                let src_host = src_host.lock().unwrap();
+                let mut dst_host = dst_host.lock().unwrap();
+                dst_host[..size as usize].copy_from_slice(&src_host[..size as usize]);
+            }
+            _ => panic!("CommandBatch::copy: src and dst GpuBuffers aren't on the same Framework"),
+        }
+
+        self
+    }
+
+    /// Submits every recorded kernel dispatch and copy as a single command submission,
+    /// returning a future that resolves once the GPU has finished executing it. Resolves
+    /// immediately if the batch only ever ran CPU-mode kernels.
+    pub async fn submit(self) {
+        let Some(encoder) = self.encoder else {
+            return;
+        };
+
+        self.fw.gpu_queue().submit(Some(encoder.finish()));
+
+        let (tx, rx) = futures::channel::oneshot::channel();
+        self.fw.gpu_queue().on_submitted_work_done(move || {
+            let _ = tx.send(());
+        });
+        rx.await
+            .expect("CommandBatch::submit: work-done callback dropped");
+    }
+}
@@ -1,10 +1,31 @@
 use std::{sync::Arc, time::Duration};
 
 // use std::sync::Arc;
-use crate::Framework;
+use crate::{batch::CommandBatch, pool::BufferPool, Framework, PoolStats};
+
+/// Set to force CPU fallback execution even when a GPU adapter is available — handy for
+/// headless/CI environments, or for debugging kernels without touching a GPU at all.
+const USE_CPU_ENV: &str = "GPGPU_USE_CPU";
 
 impl Framework {
     pub async fn default() -> Self {
+        if std::env::var(USE_CPU_ENV).is_ok() {
+            log::info!("{} set; running gpgpu in CPU fallback mode", USE_CPU_ENV);
+            return Self::cpu_only();
+        }
+
+        match Self::try_gpu().await {
+            Some(fw) => fw,
+            None => {
+                log::warn!("No compatible GPU adapter found; falling back to CPU execution");
+                Self::cpu_only()
+            }
+        }
+    }
+
+    /// Attempts to bring up a real wgpu adapter/device, returning `None` instead of panicking
+    /// so the caller can fall back to CPU execution in headless/CI environments.
+    async fn try_gpu() -> Option<Self> {
         // specify the backend, we'll just pick one from the environment i.e. whatever's available.
         let backend = wgpu::util::backend_bits_from_env().unwrap_or(wgpu::Backends::PRIMARY);
         // specify a power profile for the gpu, again pick one from the environment.
@@ -22,8 +43,7 @@ impl Framework {
                 power_preference,
                 ..Default::default()
             })
-            .await
-            .expect("Failed to find an appropriate adapter");
+            .await?;
         // create the device and queue. device is reponsible for the creation of most
         // rendering and compute resources. These are then used in commands, which are submitted to a [`Queue`].
         // `request_device` returns a Future, so must be awaited
@@ -39,7 +59,10 @@ impl Framework {
             .await
         {
             Ok((d, v)) => (d, v),
-            Err(e) => panic!("Failed at device creation: {}", e),
+            Err(e) => {
+                log::warn!("Failed at device creation: {}", e);
+                return None;
+            }
         };
 
         let device = Arc::new(device);
@@ -51,10 +74,75 @@ impl Framework {
         });
 
         // constuct and return a framework
+        Some(Framework {
+            device: Some(device),
+            queue: Some(queue),
+            adapter: Some(adapter),
+            pool: BufferPool::new(),
+            use_cpu: false,
+        })
+    }
+
+    /// Builds a `Framework` with no GPU adapter at all; every `Kernel` built on it must run via
+    /// its `Program::with_cpu_fallback` closure.
+    pub(crate) fn cpu_only() -> Self {
         Framework {
-            device,
-            queue,
-            adapter,
+            device: None,
+            queue: None,
+            adapter: None,
+            pool: BufferPool::new(),
+            use_cpu: true,
         }
     }
+
+    /// Whether `Kernel`s on this `Framework` run their CPU fallback instead of dispatching to
+    /// the GPU — true when `GPGPU_USE_CPU` forced it, or when no adapter could be found.
+    pub fn is_cpu_fallback(&self) -> bool {
+        self.use_cpu
+    }
+
+    pub(crate) fn gpu_device(&self) -> &Arc<wgpu::Device> {
+        self.device
+            .as_ref()
+            .expect("Framework has no GPU device (running in CPU fallback mode)")
+    }
+
+    pub(crate) fn gpu_queue(&self) -> &wgpu::Queue {
+        self.queue
+            .as_ref()
+            .expect("Framework has no GPU queue (running in CPU fallback mode)")
+    }
+
+    /// Requests a buffer of at least `size` bytes from the pool, falling back to `create` (which
+    /// receives the bucket's rounded-up size) when nothing pooled is free.
+    pub(crate) fn acquire_buffer(
+        &self,
+        size: u64,
+        create: impl FnOnce(u64) -> wgpu::Buffer,
+    ) -> wgpu::Buffer {
+        self.pool.acquire_or_create(size, create)
+    }
+
+    /// Returns a buffer to the pool rather than letting it drop, so the next `GpuBuffer` of the
+    /// same size class can reuse it.
+    pub(crate) fn release_buffer(&self, size: u64, buf: wgpu::Buffer) {
+        self.pool.release(size, buf);
+    }
+
+    /// Drops every buffer currently sitting idle in the pool, freeing their GPU memory.
+    pub fn clear_pool(&self) {
+        self.pool.clear();
+    }
+
+    /// Returns how much GPU memory the pool currently accounts for, split into bytes held by
+    /// live `GpuBuffer`s and bytes sitting idle and available for reuse.
+    pub fn pool_stats(&self) -> PoolStats {
+        self.pool.stats()
+    }
+
+    /// Starts a new [`CommandBatch`] for recording several kernel dispatches (and buffer copies)
+    /// into a single command submission.
+    pub fn create_batch(&self) -> CommandBatch {
+        CommandBatch::new(self)
+    }
 }
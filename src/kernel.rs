@@ -1,16 +1,25 @@
 use std::marker::PhantomData;
+use std::sync::Mutex;
 
 use thiserror::Error;
 use wgpu::{util::DeviceExt, MapMode};
 
-use crate::{DescriptorSet, Framework, GpuBuffer, GpuBufferUsage, Kernel, Program, Shader};
+use crate::{
+    reflection::BindingAccess, BufferBacking, CpuBinding, CpuKernelFn, DescriptorSet, Framework,
+    GpuBuffer, GpuBufferUsage, GpuUniformBuffer, Kernel, Program, Shader, ShaderKind, UVec3,
+};
 
+// Storage buffers stay device-local: WebGPU forbids `MAP_READ`/`MAP_WRITE` together with
+// `STORAGE`, so reads go through a separate staging buffer instead (see `GpuBuffer::read`) and
+// writes through the queue's upload path (see `GpuBuffer::write_from_slice`).
 const GPU_BUFFER_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
     wgpu::BufferUsages::STORAGE.bits()
         | wgpu::BufferUsages::COPY_SRC.bits()
-        | wgpu::BufferUsages::COPY_DST.bits()
-        | wgpu::BufferUsages::MAP_READ.bits()
-        | wgpu::BufferUsages::MAP_WRITE.bits(),
+        | wgpu::BufferUsages::COPY_DST.bits(),
+);
+
+const STAGING_READ_USAGES: wgpu::BufferUsages = wgpu::BufferUsages::from_bits_truncate(
+    wgpu::BufferUsages::MAP_READ.bits() | wgpu::BufferUsages::COPY_DST.bits(),
 );
 
 pub type BufferResult<T> = Result<T, BufferError>;
@@ -50,6 +59,92 @@ impl<'res> DescriptorSet<'res> {
         // push them into a DescriptorSet, which is a collection of bindgroup entries and their layouts.
         self.layout.push(entry_layout);
         self.set.push(entry);
+        self.cpu_bindings.push(storage_buf.as_cpu_backing());
+
+        self
+    }
+
+    /// Binds `buf` to the WGSL global variable named `name`, resolving its group, binding
+    /// index and `read`/`read_write` access from `shader`'s reflected source instead of relying
+    /// on `bind_buffer` call order matching the shader's declaration order.
+    pub fn bind_named<T>(mut self, shader: &Shader, name: &str, buf: &'res GpuBuffer<T>) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        let info = shader
+            .reflection
+            .get(name)
+            .unwrap_or_else(|| panic!("shader has no storage binding named `{name}`"));
+
+        if let Some(element_size) = info.element_size {
+            let actual = std::mem::size_of::<T>() as u64;
+            assert_eq!(
+                element_size, actual,
+                "binding `{name}`: shader declares {element_size}-byte elements, buffer has {actual}-byte elements",
+            );
+        }
+
+        match self.group {
+            Some(group) => assert_eq!(
+                group, info.group,
+                "binding `{name}` is in group {}, but this DescriptorSet is already bound to group {group}",
+                info.group,
+            ),
+            None => self.group = Some(info.group),
+        }
+
+        let entry_layout = wgpu::BindGroupLayoutEntry {
+            binding: info.binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage {
+                    read_only: info.access == BindingAccess::ReadOnly,
+                },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let entry = wgpu::BindGroupEntry {
+            binding: info.binding,
+            resource: buf.as_binding_resource(),
+        };
+
+        self.layout.push(entry_layout);
+        self.set.push(entry);
+        self.cpu_bindings.push(buf.as_cpu_backing());
+
+        self
+    }
+
+    /// Binds `uniform_buf` as a `var<uniform>` resource, for small read-only parameters
+    /// (dimensions, scalars, flags) as opposed to `bind_buffer`'s `var<storage>` bindings.
+    ///
+    /// `GpuUniformBuffer` has no CPU-fallback storage, so a `Kernel` built from a `DescriptorSet`
+    /// using this binding can only run in `ShaderKind::Wgpu` mode.
+    pub fn bind_uniform<T>(mut self, uniform_buf: &'res GpuUniformBuffer<T>) -> Self
+    where
+        T: bytemuck::Pod,
+    {
+        let bind_id = self.layout.len() as u32;
+        let entry_layout = wgpu::BindGroupLayoutEntry {
+            binding: bind_id,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let entry = wgpu::BindGroupEntry {
+            binding: bind_id,
+            resource: uniform_buf.as_binding_resource(),
+        };
+
+        self.layout.push(entry_layout);
+        self.set.push(entry);
+        self.cpu_bindings.push(None);
 
         self
     }
@@ -64,8 +159,21 @@ where
         self.as_gpu_buffer().as_entire_binding()
     }
 
-    fn as_gpu_buffer(&self) -> &wgpu::Buffer {
-        &self.buf
+    pub(crate) fn as_gpu_buffer(&self) -> &wgpu::Buffer {
+        match self.buf.as_ref().expect("GpuBuffer: buffer already released") {
+            BufferBacking::Gpu(buf) => buf,
+            BufferBacking::Cpu(_) => {
+                panic!("GpuBuffer is backed by CPU fallback storage, not a wgpu::Buffer")
+            }
+        }
+    }
+
+    /// Returns this buffer's CPU-side storage, if its `Framework` is in CPU fallback mode.
+    pub(crate) fn as_cpu_backing(&self) -> Option<&Mutex<Vec<u8>>> {
+        match self.buf.as_ref().expect("GpuBuffer: buffer already released") {
+            BufferBacking::Cpu(host) => Some(host),
+            BufferBacking::Gpu(_) => None,
+        }
     }
 
     fn size(&self) -> u64 {
@@ -76,19 +184,30 @@ where
     fn capacity(&self) -> u64 {
         self.size() / std::mem::size_of::<T>() as u64
     }
+
     /// get a GPU accessible buffer from a slice
     pub fn from_slice(fw: &'fw Framework, slice: &[T]) -> Self {
         let size = (slice.len() * std::mem::size_of::<T>()) as u64;
-        let buf = fw
-            .device
-            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some(""),
-                contents: bytemuck::cast_slice(slice),
-                usage: GPU_BUFFER_USAGES,
+        let bytes = bytemuck::cast_slice(slice);
+
+        let buf = if fw.is_cpu_fallback() {
+            BufferBacking::Cpu(Mutex::new(bytes.to_vec()))
+        } else {
+            let buf = fw.acquire_buffer(size, |bucket_size| {
+                fw.gpu_device().create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("GpuBuffer: from slice"),
+                    size: bucket_size,
+                    usage: GPU_BUFFER_USAGES,
+                    mapped_at_creation: false,
+                })
             });
+            fw.gpu_queue().write_buffer(&buf, 0, bytes);
+            BufferBacking::Gpu(buf)
+        };
+
         Self {
             fw,
-            buf,
+            buf: Some(buf),
             size,
             marker: PhantomData,
         }
@@ -96,21 +215,34 @@ where
 
     pub fn with_capacity(fw: &'fw Framework, capacity: u64) -> Self {
         let size = capacity * std::mem::size_of::<T>() as u64;
-        let buf = fw.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("GpuBuffer: with capacity"),
-            size,
-            usage: GPU_BUFFER_USAGES,
-            mapped_at_creation: false,
-        });
+
+        let buf = if fw.is_cpu_fallback() {
+            BufferBacking::Cpu(Mutex::new(vec![0u8; size as usize]))
+        } else {
+            let buf = fw.acquire_buffer(size, |bucket_size| {
+                fw.gpu_device().create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("GpuBuffer: with capacity"),
+                    size: bucket_size,
+                    usage: GPU_BUFFER_USAGES,
+                    mapped_at_creation: false,
+                })
+            });
+            BufferBacking::Gpu(buf)
+        };
+
         Self {
             fw,
-            buf,
+            buf: Some(buf),
             size,
             marker: PhantomData,
         }
     }
 
     /// Pulls some elements from the [`GpuBuffer`] into `buf`, returning how many elements were read.
+    ///
+    /// Since the storage buffer itself isn't mappable, this copies it into a throwaway
+    /// `MAP_READ` staging buffer first, via a command encoder of its own, and maps that instead.
+    /// In CPU fallback mode this just copies out of the buffer's host-visible storage directly.
     pub async fn read(&self, buf: &mut [T]) -> BufferResult<u64> {
         let output_size = (buf.len() * std::mem::size_of::<T>()) as u64;
         let download_size = if output_size > self.size {
@@ -118,8 +250,33 @@ where
         } else {
             output_size
         };
+        let elem_count = (download_size as usize) / std::mem::size_of::<T>();
+
+        if let BufferBacking::Cpu(host) = self.buf.as_ref().unwrap() {
+            let host = host.lock().unwrap();
+            buf[..elem_count].copy_from_slice(bytemuck::cast_slice(&host[..download_size as usize]));
+            return Ok(download_size);
+        }
 
-        let download = self.buf.slice(..download_size as u64);
+        // Not taken from the pool: its buckets are keyed by size only, so a pooled buffer could
+        // lack `MAP_READ`, and a staging buffer released there could come back as a storage buffer.
+        let staging = self.fw.gpu_device().create_buffer(&wgpu::BufferDescriptor {
+            label: Some("GpuBuffer: read staging buffer"),
+            size: download_size,
+            usage: STAGING_READ_USAGES,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder =
+            self.fw
+                .gpu_device()
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("GpuBuffer: read encoder"),
+                });
+        encoder.copy_buffer_to_buffer(self.as_gpu_buffer(), 0, &staging, 0, download_size);
+        self.fw.gpu_queue().submit(Some(encoder.finish()));
+
+        let download = staging.slice(0..download_size);
 
         let (tx, rx) = futures::channel::oneshot::channel();
         download.map_async(MapMode::Read, |result| {
@@ -127,7 +284,10 @@ where
         });
         rx.await
             .expect("GpuBuffer futures::channel::oneshot error")?;
-        buf.copy_from_slice(bytemuck::cast_slice(&download.get_mapped_range()));
+        buf[..elem_count].copy_from_slice(bytemuck::cast_slice(&download.get_mapped_range()));
+
+        staging.unmap();
+        drop(staging);
 
         Ok(download_size)
     }
@@ -140,18 +300,107 @@ where
 
         Ok(buf)
     }
-    // /// Blocking version of `GpuBuffer::read_vec()`.
-    // pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
-    //     futures::executor::block_on(self.read_vec())
-    // }
+
+    /// Blocking version of `GpuBuffer::read_vec()`.
+    pub fn read_vec_blocking(&self) -> BufferResult<Vec<T>> {
+        futures::executor::block_on(self.read_vec())
+    }
+
+    /// Writes `data` into the [`GpuBuffer`], starting at offset 0.
+    ///
+    /// The storage buffer keeps `COPY_DST`, so this goes straight through the queue's upload
+    /// path rather than via a `MAP_WRITE` staging buffer. In CPU fallback mode this writes
+    /// directly into the buffer's host-visible storage instead.
+    pub fn write_from_slice(&self, data: &[T]) {
+        let input_size = (data.len() * std::mem::size_of::<T>()) as u64;
+        let upload_size = if input_size > self.size {
+            self.size
+        } else {
+            input_size
+        };
+        let bytes = &bytemuck::cast_slice(data)[..upload_size as usize];
+
+        if let BufferBacking::Cpu(host) = self.buf.as_ref().unwrap() {
+            host.lock().unwrap()[..upload_size as usize].copy_from_slice(bytes);
+            return;
+        }
+
+        self.fw.gpu_queue().write_buffer(self.as_gpu_buffer(), 0, bytes);
+    }
+}
+
+impl<'fw, T> Drop for GpuBuffer<'fw, T> {
+    fn drop(&mut self) {
+        match self.buf.take() {
+            Some(BufferBacking::Gpu(buf)) => self.fw.release_buffer(self.size, buf),
+            // CPU-backed storage has no pool to return to; it's just dropped.
+            Some(BufferBacking::Cpu(_)) | None => {}
+        }
+    }
+}
+
+impl<'fw, T> GpuUniformBuffer<'fw, T>
+where
+    T: bytemuck::Pod,
+{
+    /// Uploads `slice` into a new uniform buffer.
+    pub fn from_slice(fw: &'fw Framework, slice: &[T]) -> Self {
+        let bytes = bytemuck::cast_slice(slice);
+        let buf = fw
+            .gpu_device()
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("GpuUniformBuffer: from slice"),
+                contents: bytes,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+        Self {
+            fw,
+            buf,
+            size: bytes.len() as u64,
+            marker: PhantomData,
+        }
+    }
+
+    /// Overwrites the buffer's contents with `data`, starting at offset 0.
+    pub fn write_from_slice(&self, data: &[T]) {
+        let input_size = (data.len() * std::mem::size_of::<T>()) as u64;
+        let upload_size = if input_size > self.size {
+            self.size
+        } else {
+            input_size
+        };
+        let bytes = &bytemuck::cast_slice(data)[..upload_size as usize];
+
+        self.fw.gpu_queue().write_buffer(&self.buf, 0, bytes);
+    }
+
+    pub(crate) fn as_binding_resource(&self) -> wgpu::BindingResource {
+        self.buf.as_entire_binding()
+    }
 }
 
 impl<'sha, 'res> Program<'sha, 'res> {
     pub fn new(shader: &'sha Shader, entry_point: impl Into<String>) -> Self {
+        let entry_point = entry_point.into();
+
+        // An empty entry-point list means reflection couldn't parse the shader, not that it
+        // declares zero entry points, so only validate when we actually have something to
+        // check against.
+        let known_entry_points = shader.reflection.entry_points();
+        if !known_entry_points.is_empty() {
+            assert!(
+                known_entry_points.iter().any(|ep| ep == &entry_point),
+                "shader has no entry point named `{entry_point}` (found: {known_entry_points:?})",
+            );
+        }
+
         Self {
             shader,
-            entry_point: entry_point.into(),
+            entry_point,
             descriptors: Vec::new(),
+            cpu_kernel: None,
+            push_constants: None,
         }
     }
 
@@ -161,21 +410,135 @@ impl<'sha, 'res> Program<'sha, 'res> {
         self.descriptors.push(desc);
         self
     }
+
+    /// Registers a CPU implementation of this program's shader, invoked once per workgroup when
+    /// the `Kernel` built from this `Program` runs on a CPU-fallback `Framework`
+    /// (see [`Framework::is_cpu_fallback`]).
+    pub fn with_cpu_fallback(
+        mut self,
+        kernel_fn: impl for<'a> Fn(UVec3, &[CpuBinding<'a>]) + Send + Sync + 'static,
+    ) -> Self {
+        self.cpu_kernel = Some(Box::new(kernel_fn));
+        self
+    }
+
+    /// Registers raw push-constant bytes for this program's shader. Wired into the pipeline's
+    /// `push_constant_ranges` and issued via `set_push_constants` on every `Kernel::enqueue`,
+    /// when the `Framework`'s adapter supports `wgpu::Features::PUSH_CONSTANTS` — for this path,
+    /// write the shader's parameters as a `var<push_constant>`.
+    ///
+    /// On adapters without that feature, `Kernel::new` instead falls back to an internal
+    /// [`crate::GpuUniformBuffer`] bound at the shader's `var<uniform> push_constants: ...;`
+    /// declaration (resolved by name via reflection, the same way [`DescriptorSet::bind_named`]
+    /// resolves storage bindings), so a shader meant to run on both kinds of adapter should
+    /// declare that uniform at `@group(N)` where `N` is this `Program`'s descriptor count, in
+    /// addition to its `push_constant` declaration. `Kernel::new` panics if the fallback is
+    /// needed and the shader declares no such uniform.
+    pub fn set_push_constants(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.push_constants = Some(data.into());
+        self
+    }
 }
 
-impl<'sha, 'res, 'fw> Kernel<'fw> {
+impl<'sha, 'res, 'fw> Kernel<'fw, 'res> {
     pub fn new(fw: &'fw Framework, program: Program<'sha, 'res>) -> Self {
+        // Validate that every storage binding the shader declares was actually bound, rather than
+        // letting a missing one fail opaquely at pipeline creation.
+        //
+        // `bind_named` resolves each binding's `@group` from reflection, but a `DescriptorSet`'s
+        // position in `program.descriptors` is what actually determines its wgpu bind-group slot
+        // (`compute_pass.set_bind_group(set_id, ...)` in `enqueue_wgpu`). So a `DescriptorSet`
+        // must sit at the same index as the `@group` it was reflected against, or bindings land
+        // in the wrong slot at dispatch time while this validation would otherwise still pass.
+        for (set_id, desc) in program.descriptors.iter().enumerate() {
+            if let Some(group) = desc.group {
+                assert_eq!(
+                    set_id as u32, group,
+                    "DescriptorSet for @group({group}) was added at position {set_id}; \
+                     add_descriptor calls must match shader @group order",
+                );
+            }
+        }
+
+        let bound: std::collections::HashSet<(u32, u32)> = program
+            .descriptors
+            .iter()
+            .enumerate()
+            .flat_map(|(set_id, desc)| {
+                desc.layout
+                    .iter()
+                    .map(move |entry| (set_id as u32, entry.binding))
+            })
+            .collect();
+        for (group, binding) in program.shader.reflection.declared_bindings() {
+            assert!(
+                bound.contains(&(group, binding)),
+                "shader declares a binding at group {group}, binding {binding} that no DescriptorSet bound",
+            );
+        }
+
+        let mode = if fw.is_cpu_fallback() {
+            ShaderKind::Cpu
+        } else {
+            ShaderKind::Wgpu
+        };
+
+        if mode == ShaderKind::Cpu {
+            let cpu_kernel = program.cpu_kernel.unwrap_or_else(|| {
+                panic!(
+                    "Framework is in CPU fallback mode, but this Program has no CPU \
+                     implementation (see Program::with_cpu_fallback)"
+                )
+            });
+            let cpu_bindings: Vec<Vec<&'res Mutex<Vec<u8>>>> = program
+                .descriptors
+                .iter()
+                .map(|desc| {
+                    desc.cpu_bindings
+                        .iter()
+                        .map(|m| m.expect("bound GpuBuffer has no CPU-side storage"))
+                        .collect()
+                })
+                .collect();
+
+            // `enqueue_cpu` locks every bound buffer's Mutex once per workgroup; `Mutex` isn't
+            // reentrant, so a GpuBuffer bound more than once in this Program (e.g. as both an
+            // input and an output) would deadlock on the second lock. Reject that eagerly
+            // instead of hanging at dispatch time.
+            let mut seen = std::collections::HashSet::new();
+            for mutex in cpu_bindings.iter().flatten() {
+                assert!(
+                    seen.insert(*mutex as *const Mutex<Vec<u8>>),
+                    "the same GpuBuffer is bound more than once in this Program; CPU fallback \
+                     kernels can't lock the same buffer's Mutex twice (in-place \
+                     read-modify-write isn't supported in CPU fallback mode)",
+                );
+            }
+
+            return Self {
+                fw,
+                mode,
+                pipeline: None,
+                bindgroups: Vec::new(),
+                cpu_kernel: Some(cpu_kernel),
+                cpu_bindings,
+                entry_point: program.entry_point,
+                push_constants: None,
+                push_constants_fallback: None,
+            };
+        }
+
         let mut bindgroup_layouts = Vec::new();
         let mut bindgroups = Vec::new();
 
         for (set_id, desc) in program.descriptors.iter().enumerate() {
             let bindgroup_layout =
-                fw.device
+                fw.gpu_device()
                     .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                         label: None,
                         entries: &desc.layout,
                     });
-            let bind_group = fw.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            let bind_group = fw.gpu_device().create_bind_group(&wgpu::BindGroupDescriptor {
                 label: None,
                 layout: &bindgroup_layout,
                 entries: &desc.set,
@@ -187,55 +550,228 @@ impl<'sha, 'res, 'fw> Kernel<'fw> {
             bindgroups.push(bind_group);
         }
 
-        let bindgroup_layouts = bindgroup_layouts.iter().collect::<Vec<_>>();
+        // Either issued via `set_push_constants` directly (when the adapter supports it), or
+        // uploaded into a fallback uniform buffer bound as one more bind group (when it doesn't).
+        let mut push_constants = None;
+        let mut push_constants_fallback = None;
+        let mut push_constant_ranges: Vec<wgpu::PushConstantRange> = Vec::new();
+
+        if let Some(data) = program.push_constants {
+            if fw.gpu_device().features().contains(wgpu::Features::PUSH_CONSTANTS) {
+                push_constant_ranges.push(wgpu::PushConstantRange {
+                    stages: wgpu::ShaderStages::COMPUTE,
+                    range: 0..data.len() as u32,
+                });
+                push_constants = Some(data);
+            } else {
+                log::warn!(
+                    "adapter has no PUSH_CONSTANTS feature; Program::set_push_constants is \
+                     falling back to the shader's `push_constants` uniform binding"
+                );
+
+                let (group, binding) =
+                    program.shader.reflection.push_constants_binding().unwrap_or_else(|| {
+                        panic!(
+                            "adapter has no PUSH_CONSTANTS feature and this shader declares no \
+                             `var<uniform> push_constants: ...;` for Program::set_push_constants \
+                             to fall back onto"
+                        )
+                    });
+                let expected_group = program.descriptors.len() as u32;
+                assert_eq!(
+                    group, expected_group,
+                    "shader's `push_constants` uniform must be declared at @group({expected_group}) \
+                     (immediately after this Kernel's {expected_group} DescriptorSet(s)), but was \
+                     found at @group({group})",
+                );
+
+                let fallback = GpuUniformBuffer::<u8>::from_slice(fw, &data);
+                let fallback_layout =
+                    fw.gpu_device()
+                        .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                            label: Some("Kernel: push-constants fallback layout"),
+                            entries: &[wgpu::BindGroupLayoutEntry {
+                                binding,
+                                visibility: wgpu::ShaderStages::COMPUTE,
+                                ty: wgpu::BindingType::Buffer {
+                                    ty: wgpu::BufferBindingType::Uniform,
+                                    has_dynamic_offset: false,
+                                    min_binding_size: None,
+                                },
+                                count: None,
+                            }],
+                        });
+                let fallback_bindgroup = fw.gpu_device().create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Kernel: push-constants fallback bind group"),
+                    layout: &fallback_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding,
+                        resource: fallback.as_binding_resource(),
+                    }],
+                });
+
+                bindgroup_layouts.push(fallback_layout);
+                bindgroups.push(fallback_bindgroup);
+                push_constants_fallback = Some(fallback);
+            }
+        }
+
+        let bindgroup_layouts_ref = bindgroup_layouts.iter().collect::<Vec<_>>();
 
         // create the pipeline
         let compute_pipeline_layout =
-            fw.device
+            fw.gpu_device()
                 .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                     label: Some(""),
-                    bind_group_layouts: &bindgroup_layouts,
-                    push_constant_ranges: &[],
+                    bind_group_layouts: &bindgroup_layouts_ref,
+                    push_constant_ranges: &push_constant_ranges,
                 });
         let compute_pipeline =
-            fw.device
+            fw.gpu_device()
                 .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
                     label: Some(""),
                     layout: Some(&compute_pipeline_layout),
-                    module: &program.shader.0,
+                    module: &program.shader.module,
                     entry_point: &program.entry_point,
                 });
 
         Self {
             fw,
-            pipeline: compute_pipeline,
+            mode,
+            pipeline: Some(compute_pipeline),
             bindgroups,
+            cpu_kernel: None,
+            cpu_bindings: Vec::new(),
             entry_point: program.entry_point,
+            push_constants,
+            push_constants_fallback,
         }
     }
 
     pub fn enqueue(&self, x: u32, y: u32, z: u32) {
-        let mut encoder = self
-            .fw
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("compute encoder"),
-            });
+        match self.mode {
+            ShaderKind::Cpu => self.enqueue_cpu(x, y, z),
+            ShaderKind::Wgpu => self.enqueue_wgpu(x, y, z),
+        }
+    }
+
+    /// Runs the registered CPU fallback once per workgroup in the `x * y * z` grid, the same
+    /// iteration space `dispatch_workgroups` would cover on the GPU.
+    fn enqueue_cpu(&self, x: u32, y: u32, z: u32) {
+        let kernel_fn: &CpuKernelFn = self
+            .cpu_kernel
+            .as_ref()
+            .expect("Kernel has no CPU implementation");
+
+        for wz in 0..z {
+            for wy in 0..y {
+                for wx in 0..x {
+                    let bindings: Vec<CpuBinding> = self
+                        .cpu_bindings
+                        .iter()
+                        .flatten()
+                        .map(|m| CpuBinding(m.lock().unwrap()))
+                        .collect();
+                    kernel_fn(UVec3::new(wx, wy, wz), &bindings);
+                }
+            }
+        }
+    }
+
+    fn enqueue_wgpu(&self, x: u32, y: u32, z: u32) {
+        let mut encoder =
+            self.fw
+                .gpu_device()
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("compute encoder"),
+                });
         {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("begin compute pass encoding"),
             });
 
-            compute_pass.set_pipeline(&self.pipeline);
+            compute_pass.set_pipeline(self.pipeline.as_ref().expect("Kernel has no GPU pipeline"));
 
             for (id, bindgroup) in self.bindgroups.iter().enumerate() {
                 compute_pass.set_bind_group(id as u32, bindgroup, &[]);
             }
 
+            if let Some(data) = &self.push_constants {
+                compute_pass.set_push_constants(0, data);
+            }
+
             compute_pass.insert_debug_marker(&self.entry_point);
             compute_pass.dispatch_workgroups(x, y, z);
         }
 
-        self.fw.queue.submit(Some(encoder.finish()));
+        self.fw.gpu_queue().submit(Some(encoder.finish()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{atomic::AtomicUsize, atomic::Ordering, Arc};
+
+    /// Builds a `Kernel` directly, bypassing `Program`/`Shader` (which require a real GPU
+    /// device even in CPU fallback mode), to exercise `enqueue_cpu`'s dispatch loop in isolation.
+    fn cpu_kernel(fw: &Framework, cpu_kernel: CpuKernelFn) -> Kernel<'_, 'static> {
+        Kernel {
+            fw,
+            mode: ShaderKind::Cpu,
+            pipeline: None,
+            bindgroups: Vec::new(),
+            cpu_kernel: Some(cpu_kernel),
+            cpu_bindings: Vec::new(),
+            entry_point: String::new(),
+            push_constants: None,
+            push_constants_fallback: None,
+        }
+    }
+
+    #[test]
+    fn enqueue_cpu_invokes_closure_once_per_workgroup_in_the_dispatch_grid() {
+        let fw = Framework::cpu_only();
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let (cc, sn) = (Arc::clone(&call_count), Arc::clone(&seen));
+        let kernel = cpu_kernel(
+            &fw,
+            Box::new(move |id, _bindings| {
+                cc.fetch_add(1, Ordering::Relaxed);
+                sn.lock().unwrap().push(id);
+            }),
+        );
+
+        kernel.enqueue(2, 3, 1);
+
+        assert_eq!(call_count.load(Ordering::Relaxed), 2 * 3 * 1);
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 6);
+        for wy in 0..3 {
+            for wx in 0..2 {
+                assert!(seen.contains(&UVec3::new(wx, wy, 0)));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Kernel has no CPU implementation")]
+    fn enqueue_cpu_without_a_registered_closure_panics() {
+        let fw = Framework::cpu_only();
+        let kernel: Kernel<'_, 'static> = Kernel {
+            fw: &fw,
+            mode: ShaderKind::Cpu,
+            pipeline: None,
+            bindgroups: Vec::new(),
+            cpu_kernel: None,
+            cpu_bindings: Vec::new(),
+            entry_point: String::new(),
+            push_constants: None,
+            push_constants_fallback: None,
+        };
+
+        kernel.enqueue(1, 1, 1);
     }
 }
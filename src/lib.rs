@@ -1,31 +1,86 @@
 #![allow(warnings)]
 
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, sync::{Arc, Mutex}};
 
 use wgpu::{BindGroupLayoutEntry, BindGroupEntry, BindGroup};
 
 pub mod shader;
 pub mod framework;
 pub mod kernel;
- 
+pub mod pool;
+pub mod reflection;
+pub mod batch;
+
+pub use batch::CommandBatch;
+
+pub use pool::PoolStats;
+pub use glam::UVec3;
+
+/// Which path a [`Kernel`] dispatches through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderKind {
+    /// Runs as a real compute pass on the GPU.
+    Wgpu,
+    /// Runs as a CPU fallback, e.g. because [`Framework::is_cpu_fallback`] is set.
+    Cpu,
+}
+
+/// A bound resource as seen by a CPU-executed kernel: the buffer's host-visible bytes, locked
+/// for the duration of one workgroup invocation.
+///
+/// Derefs to `[u8]`/`&mut [u8]`, the same raw storage a GPU-bound [`GpuBuffer`] would expose to
+/// the shader, so a CPU kernel closure can reinterpret it with [`bytemuck`] as needed.
+pub struct CpuBinding<'a>(std::sync::MutexGuard<'a, Vec<u8>>);
+
+impl<'a> std::ops::Deref for CpuBinding<'a> {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<'a> std::ops::DerefMut for CpuBinding<'a> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+/// A `Program`'s CPU fallback: invoked once per workgroup in the dispatch grid.
+pub(crate) type CpuKernelFn = Box<dyn for<'a> Fn(UVec3, &[CpuBinding<'a>]) + Send + Sync>;
+
 /// Represents a shader.
 ///
-/// It's just a wrapper around [`wgpu::ShaderModule`].
-pub struct Shader(wgpu::ShaderModule);
+/// Wraps a [`wgpu::ShaderModule`] together with binding metadata reflected from its WGSL,
+/// SPIR-V or GLSL source, so bindings can be resolved by name via [`DescriptorSet::bind_named`].
+pub struct Shader {
+    module: wgpu::ShaderModule,
+    reflection: reflection::ShaderReflection,
+}
 
 /// Entry point of `gpgpu`. A [`Framework`] must be created
 /// first as all GPU primitives needs it to be created.
+///
+/// `device`/`queue`/`adapter` are `None` when running in CPU fallback mode (see
+/// [`Framework::is_cpu_fallback`]), since no adapter was requested in the first place.
 pub struct Framework {
-    device: Arc<wgpu::Device>,
-    queue: wgpu::Queue,
-    adapter: wgpu::Adapter,
+    device: Option<Arc<wgpu::Device>>,
+    queue: Option<wgpu::Queue>,
+    adapter: Option<wgpu::Adapter>,
+    pool: pool::BufferPool,
+    use_cpu: bool,
 }
 
-/// Holds `BindGroupEntries` and their layout. DescriptorSet is basically used to bind resources as entries.  
+/// Holds `BindGroupEntries` and their layout. DescriptorSet is basically used to bind resources as entries.
 #[derive(Default, Debug)]
 pub struct DescriptorSet<'a> {
     layout: Vec<BindGroupLayoutEntry>,
-    set: Vec<BindGroupEntry<'a>>
+    set: Vec<BindGroupEntry<'a>>,
+    // Recorded from the first `bind_named` call and checked against every later one, since a
+    // DescriptorSet corresponds to a single `@group`.
+    group: Option<u32>,
+    // Parallel to `set`: each bound buffer's CPU-side storage, when it has one. Only read back
+    // when a `Kernel` runs its `ShaderKind::Cpu` path.
+    cpu_bindings: Vec<Option<&'a Mutex<Vec<u8>>>>,
 }
 
 #[derive(PartialEq, Eq)]
@@ -44,13 +99,35 @@ pub enum GpuBufferUsage {
     ReadWrite,
 }
 
-/// Vector of contiguous homogeneous elements on GPU memory.
+/// Either a real `wgpu::Buffer` or, when its `Framework` is in CPU fallback mode, a plain byte
+/// vector a CPU kernel closure can read/write directly.
+pub(crate) enum BufferBacking {
+    Gpu(wgpu::Buffer),
+    Cpu(Mutex<Vec<u8>>),
+}
+
+/// Vector of contiguous homogeneous elements on GPU memory (or, in CPU fallback mode, host
+/// memory with the same layout).
 /// Its elements must implement [`bytemuck::Pod`].
 ///
 /// Equivalent to OpenCL's Buffer objects.
 ///
 /// Basically wraps a [`wgpu::Buffer`] i.e. a gpu accessible buffer.
 pub struct GpuBuffer<'fw, T> {
+    fw: &'fw Framework,
+    // `None` only transiently, while `Drop::drop` hands a `Gpu` buffer back to the pool.
+    buf: Option<BufferBacking>,
+    size: u64,
+    marker: PhantomData<T>,
+}
+
+/// Small read-only parameter buffer for a `var<uniform>` binding (dimensions, scalars, flags),
+/// as distinct from [`GpuBuffer`]'s `var<storage>` bindings.
+///
+/// Bound via [`DescriptorSet::bind_uniform`]. Unlike `GpuBuffer`, it doesn't go through
+/// [`Framework`]'s buffer pool (uniform buffers are small and short-lived by convention) and has
+/// no CPU fallback backing, since it always requires a real GPU device.
+pub struct GpuUniformBuffer<'fw, T> {
     fw: &'fw Framework,
     buf: wgpu::Buffer,
     size: u64,
@@ -62,12 +139,26 @@ pub struct Program<'sha, 'res> {
     shader: &'sha Shader,
     entry_point: String,
     descriptors: Vec<DescriptorSet<'res>>,
+    cpu_kernel: Option<CpuKernelFn>,
+    push_constants: Option<Vec<u8>>,
 }
 
 /// dispatches the shader with its bindings
-pub struct Kernel<'fw> {
+pub struct Kernel<'fw, 'res> {
     fw: &'fw Framework,
-    pipeline: wgpu::ComputePipeline,
+    mode: ShaderKind,
+    // `Wgpu` mode only.
+    pipeline: Option<wgpu::ComputePipeline>,
     bindgroups: Vec<BindGroup>,
-    entry_point: String
+    // `Cpu` mode only: one entry per descriptor set, each holding that set's bound buffers in
+    // the order they were bound.
+    cpu_kernel: Option<CpuKernelFn>,
+    cpu_bindings: Vec<Vec<&'res Mutex<Vec<u8>>>>,
+    entry_point: String,
+    // `Wgpu` mode only: raw bytes issued via `set_push_constants` on every `enqueue`, when the
+    // adapter supports `wgpu::Features::PUSH_CONSTANTS`.
+    push_constants: Option<Vec<u8>>,
+    // `Wgpu` mode only: keeps the push-constants fallback uniform buffer (bound as an extra bind
+    // group) alive for the Kernel's lifetime, when `PUSH_CONSTANTS` isn't supported.
+    push_constants_fallback: Option<GpuUniformBuffer<'fw, u8>>,
 }
\ No newline at end of file
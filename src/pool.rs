@@ -0,0 +1,185 @@
+//! A simple slab-style free-list for [`wgpu::Buffer`]s, so repeated allocation/drop cycles
+//! (e.g. one `GpuBuffer` per dispatch in a streaming workload) reuse GPU memory instead of
+//! growing it every iteration.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Buffers larger than this are kept in their own exact-size bucket rather than rounded up to
+/// the next power of two, since rounding a one-off multi-megabyte allocation up could waste a
+/// lot of GPU memory for no reuse benefit.
+const LARGE_BUFFER_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+/// Snapshot of how much GPU memory the pool is responsible for.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoolStats {
+    /// Bytes held by buffers currently checked out to a live [`crate::GpuBuffer`].
+    pub live_bytes: u64,
+    /// Bytes held by buffers sitting idle in the pool, available for reuse.
+    pub pooled_bytes: u64,
+}
+
+/// Size-bucketed free-list of pooled [`wgpu::Buffer`]s, owned by [`crate::Framework`].
+pub(crate) struct BufferPool {
+    buckets: Mutex<HashMap<u64, Vec<wgpu::Buffer>>>,
+    live_bytes: AtomicU64,
+    pooled_bytes: AtomicU64,
+}
+
+impl BufferPool {
+    pub(crate) fn new() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            live_bytes: AtomicU64::new(0),
+            pooled_bytes: AtomicU64::new(0),
+        }
+    }
+
+    /// Rounds `size` up to the bucket it would be pooled under.
+    fn bucket_key(size: u64) -> u64 {
+        if size > LARGE_BUFFER_THRESHOLD {
+            size
+        } else {
+            size.next_power_of_two().max(1)
+        }
+    }
+
+    /// Returns a buffer of at least `size` bytes, reusing a pooled one if the bucket has one
+    /// free, otherwise calling `create` with the bucket's (rounded-up) size.
+    pub(crate) fn acquire_or_create(
+        &self,
+        size: u64,
+        create: impl FnOnce(u64) -> wgpu::Buffer,
+    ) -> wgpu::Buffer {
+        let key = Self::bucket_key(size);
+
+        let pooled = self
+            .buckets
+            .lock()
+            .unwrap()
+            .get_mut(&key)
+            .and_then(|free| free.pop());
+
+        let buf = match pooled {
+            Some(buf) => {
+                self.pooled_bytes.fetch_sub(key, Ordering::Relaxed);
+                buf
+            }
+            None => create(key),
+        };
+
+        self.live_bytes.fetch_add(key, Ordering::Relaxed);
+        buf
+    }
+
+    /// Returns a buffer to the pool for later reuse rather than dropping it.
+    pub(crate) fn release(&self, size: u64, buf: wgpu::Buffer) {
+        let key = Self::bucket_key(size);
+        self.buckets.lock().unwrap().entry(key).or_default().push(buf);
+        self.live_bytes.fetch_sub(key, Ordering::Relaxed);
+        self.pooled_bytes.fetch_add(key, Ordering::Relaxed);
+    }
+
+    /// Drops every buffer currently sitting idle in the pool, freeing their GPU memory.
+    pub(crate) fn clear(&self) {
+        self.buckets.lock().unwrap().clear();
+        self.pooled_bytes.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        PoolStats {
+            live_bytes: self.live_bytes.load(Ordering::Relaxed),
+            pooled_bytes: self.pooled_bytes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_key_rounds_small_sizes_up_to_next_power_of_two() {
+        assert_eq!(BufferPool::bucket_key(0), 1);
+        assert_eq!(BufferPool::bucket_key(1), 1);
+        assert_eq!(BufferPool::bucket_key(5), 8);
+        assert_eq!(BufferPool::bucket_key(8), 8);
+        assert_eq!(BufferPool::bucket_key(9), 16);
+    }
+
+    #[test]
+    fn bucket_key_keeps_large_buffers_at_their_exact_size() {
+        let size = LARGE_BUFFER_THRESHOLD + 1;
+        assert_eq!(BufferPool::bucket_key(size), size);
+    }
+
+    /// `acquire_or_create`/`release` only deal in real `wgpu::Buffer`s, which only a
+    /// `wgpu::Device` can create; skip on a machine with no adapter (e.g. a headless CI runner),
+    /// the same circumstance `Framework::try_gpu` falls back to CPU execution for.
+    fn test_device() -> Option<wgpu::Device> {
+        futures::executor::block_on(async {
+            let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+                backends: wgpu::Backends::all(),
+                dx12_shader_compiler: wgpu::Dx12Compiler::default(),
+            });
+            let adapter = instance
+                .request_adapter(&wgpu::RequestAdapterOptions::default())
+                .await?;
+            let (device, _queue) = adapter
+                .request_device(
+                    &wgpu::DeviceDescriptor {
+                        label: Some("pool test device"),
+                        features: adapter.features(),
+                        limits: adapter.limits(),
+                    },
+                    None,
+                )
+                .await
+                .ok()?;
+            Some(device)
+        })
+    }
+
+    #[test]
+    fn acquire_or_create_reuses_released_buffers_in_the_same_bucket() {
+        let Some(device) = test_device() else {
+            eprintln!("skipping: no GPU adapter available");
+            return;
+        };
+        let make = |size: u64| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: None,
+                size,
+                usage: wgpu::BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+        };
+
+        let pool = BufferPool::new();
+
+        let buf = pool.acquire_or_create(10, make);
+        assert_eq!(pool.stats().live_bytes, 16);
+        assert_eq!(pool.stats().pooled_bytes, 0);
+
+        pool.release(10, buf);
+        assert_eq!(pool.stats().live_bytes, 0);
+        assert_eq!(pool.stats().pooled_bytes, 16);
+
+        let mut created = false;
+        let reused = pool.acquire_or_create(10, |size| {
+            created = true;
+            make(size)
+        });
+        assert!(
+            !created,
+            "acquire_or_create should have reused the released buffer instead of creating a new one"
+        );
+        assert_eq!(pool.stats().live_bytes, 16);
+        assert_eq!(pool.stats().pooled_bytes, 0);
+
+        pool.release(10, reused);
+        pool.clear();
+        assert_eq!(pool.stats().pooled_bytes, 0);
+    }
+}
@@ -0,0 +1,205 @@
+//! Reflects a shader module with `naga` so bindings can be resolved by the variable name a
+//! shader declares, instead of relying on `DescriptorSet::bind_buffer` calls matching the
+//! shader's `@group`/`@binding` declaration order. Works the same whether the module came from
+//! WGSL, SPIR-V, or GLSL, since all three front ends produce the same `naga::Module` IR.
+
+use std::collections::HashMap;
+
+/// Whether a shader's binding is declared `read` or `read_write`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindingAccess {
+    ReadOnly,
+    ReadWrite,
+}
+
+/// Reflected metadata for a single `@group(..) @binding(..)` resource.
+#[derive(Debug, Clone, Copy)]
+pub struct BindingInfo {
+    pub group: u32,
+    pub binding: u32,
+    pub access: BindingAccess,
+    /// Byte size of one element of the bound array, when the shader's type makes that
+    /// statically known (e.g. `array<u32>`); `None` for types reflection can't size this way.
+    pub element_size: Option<u64>,
+}
+
+/// Maps a shader's global resource names to their reflected binding metadata, and lists its
+/// entry points.
+#[derive(Debug, Clone, Default)]
+pub struct ShaderReflection {
+    bindings: HashMap<String, BindingInfo>,
+    entry_points: Vec<String>,
+    // `@group`/`@binding` of a `var<uniform>` named `push_constants`, if the shader declares
+    // one. This is the well-known fallback slot `Kernel::new` uses for
+    // `Program::set_push_constants` on adapters without `wgpu::Features::PUSH_CONSTANTS`.
+    push_constants_binding: Option<(u32, u32)>,
+}
+
+impl ShaderReflection {
+    /// Parses WGSL `source` with `naga` to recover binding and entry-point metadata.
+    ///
+    /// Reflection is best-effort: `source` has already been handed to `wgpu` to create the
+    /// actual [`wgpu::ShaderModule`], so a parse failure here just yields an empty table rather
+    /// than a second, confusing error for the same bad shader.
+    pub(crate) fn from_wgsl(source: &str) -> Self {
+        match naga::front::wgsl::parse_str(source) {
+            Ok(module) => Self::from_naga_module(&module),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Parses raw SPIR-V `words` with `naga` to recover binding and entry-point metadata.
+    pub(crate) fn from_spirv(words: &[u32]) -> Self {
+        match naga::front::spv::parse_u8_slice(bytemuck::cast_slice(words), &Default::default()) {
+            Ok(module) => Self::from_naga_module(&module),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Parses GLSL `source` for the given shader `stage` with `naga` to recover binding and
+    /// entry-point metadata. Gated behind the same `glsl` feature as `Shader::from_glsl`.
+    #[cfg(feature = "glsl")]
+    pub(crate) fn from_glsl(source: &str, stage: naga::ShaderStage) -> Self {
+        let options = naga::front::glsl::Options::from(stage);
+        match naga::front::glsl::Frontend::default().parse(&options, source) {
+            Ok(module) => Self::from_naga_module(&module),
+            Err(_) => Self::default(),
+        }
+    }
+
+    fn from_naga_module(module: &naga::Module) -> Self {
+        let mut bindings = HashMap::new();
+        let mut push_constants_binding = None;
+
+        for (_, var) in module.global_variables.iter() {
+            let (Some(name), Some(binding)) = (var.name.clone(), var.binding.clone()) else {
+                continue;
+            };
+
+            match var.space {
+                naga::AddressSpace::Storage { access } => {
+                    let access = if access.contains(naga::StorageAccess::STORE) {
+                        BindingAccess::ReadWrite
+                    } else {
+                        BindingAccess::ReadOnly
+                    };
+                    let element_size = match &module.types[var.ty].inner {
+                        naga::TypeInner::Array { stride, .. } => Some(*stride as u64),
+                        _ => None,
+                    };
+
+                    bindings.insert(
+                        name,
+                        BindingInfo {
+                            group: binding.group,
+                            binding: binding.binding,
+                            access,
+                            element_size,
+                        },
+                    );
+                }
+                // The well-known uniform fallback binding `Kernel::new` looks for when wiring up
+                // `Program::set_push_constants` on adapters without `PUSH_CONSTANTS` support.
+                naga::AddressSpace::Uniform if name == "push_constants" => {
+                    push_constants_binding = Some((binding.group, binding.binding));
+                }
+                // Other uniforms and push constants aren't storage bindings; `bind_named`
+                // doesn't apply to them.
+                _ => {}
+            }
+        }
+
+        let entry_points = module
+            .entry_points
+            .iter()
+            .map(|ep| ep.name.clone())
+            .collect();
+
+        Self {
+            bindings,
+            entry_points,
+            push_constants_binding,
+        }
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&BindingInfo> {
+        self.bindings.get(name)
+    }
+
+    /// Every `(group, binding)` pair of the shader's storage bindings, for validating that a
+    /// [`crate::Kernel`] being built actually has a bound resource for each one. Uniform bindings
+    /// are not included.
+    pub(crate) fn declared_bindings(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.bindings.values().map(|info| (info.group, info.binding))
+    }
+
+    /// Entry points discovered in the module. Empty when reflection couldn't parse the source
+    /// (rather than the module genuinely declaring none), so callers should treat an empty list
+    /// as "unknown", not "invalid".
+    pub(crate) fn entry_points(&self) -> &[String] {
+        &self.entry_points
+    }
+
+    /// `@group`/`@binding` of this shader's `var<uniform> push_constants`, if it declares one.
+    pub(crate) fn push_constants_binding(&self) -> Option<(u32, u32)> {
+        self.push_constants_binding
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WGSL: &str = r#"
+        @group(0) @binding(0)
+        var<storage, read> input: array<u32>;
+
+        @group(0) @binding(1)
+        var<storage, read_write> output: array<u32>;
+
+        @group(1) @binding(0)
+        var<uniform> push_constants: vec4<u32>;
+
+        @compute @workgroup_size(64)
+        fn main() {}
+    "#;
+
+    #[test]
+    fn from_wgsl_reflects_storage_bindings() {
+        let reflection = ShaderReflection::from_wgsl(WGSL);
+
+        let input = reflection.get("input").expect("input binding");
+        assert_eq!((input.group, input.binding), (0, 0));
+        assert_eq!(input.access, BindingAccess::ReadOnly);
+        assert_eq!(input.element_size, Some(4));
+
+        let output = reflection.get("output").expect("output binding");
+        assert_eq!((output.group, output.binding), (0, 1));
+        assert_eq!(output.access, BindingAccess::ReadWrite);
+        assert_eq!(output.element_size, Some(4));
+
+        assert!(reflection.get("push_constants").is_none());
+    }
+
+    #[test]
+    fn from_wgsl_reflects_entry_points() {
+        let reflection = ShaderReflection::from_wgsl(WGSL);
+        assert_eq!(reflection.entry_points(), &["main".to_string()]);
+    }
+
+    #[test]
+    fn from_wgsl_reflects_push_constants_uniform_binding() {
+        let reflection = ShaderReflection::from_wgsl(WGSL);
+        assert_eq!(reflection.push_constants_binding(), Some((1, 0)));
+    }
+
+    #[test]
+    fn from_wgsl_on_unparsable_source_yields_empty_reflection() {
+        let reflection = ShaderReflection::from_wgsl("not valid wgsl {{{");
+
+        assert!(reflection.get("input").is_none());
+        assert!(reflection.entry_points().is_empty());
+        assert!(reflection.push_constants_binding().is_none());
+        assert!(reflection.declared_bindings().next().is_none());
+    }
+}
@@ -1,5 +1,5 @@
 
-use crate::{Shader, Framework};
+use crate::{reflection::ShaderReflection, Shader, Framework};
 
 use std::{path::Path, borrow::Cow};
 
@@ -10,10 +10,59 @@ pub fn from_wgsl_file(fw: &Framework, path: impl AsRef<Path>) -> std::io::Result
     let source_string = std::fs::read_to_string(&path)?;
     let shader_name = path.as_ref().to_str();
 
-    Ok(Self(fw.device.create_shader_module(
-        wgpu::ShaderModuleDescriptor {
-            label: shader_name,
-            source: wgpu::ShaderSource::Wgsl(Cow::Owned(source_string)),
+    let reflection = ShaderReflection::from_wgsl(&source_string);
+    let module = fw.gpu_device().create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: shader_name,
+        source: wgpu::ShaderSource::Wgsl(Cow::Owned(source_string)),
+    });
+
+    Ok(Self { module, reflection })
+}
+
+/// Initialises a [`Shader`] from raw SPIR-V words, e.g. as emitted by `rust-gpu`.
+pub fn from_spirv(fw: &Framework, words: &[u32]) -> Self {
+    let reflection = ShaderReflection::from_spirv(words);
+    let module = fw.gpu_device().create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Shader: from SPIR-V"),
+        source: wgpu::ShaderSource::SpirV(Cow::Borrowed(words)),
+    });
+
+    Self { module, reflection }
+}
+
+/// Initialises a [`Shader`] from a file containing raw SPIR-V bytes.
+pub fn from_spirv_file(fw: &Framework, path: impl AsRef<Path>) -> std::io::Result<Self> {
+    let bytes = std::fs::read(&path)?;
+    let source = wgpu::util::make_spirv(&bytes);
+    let words = match &source {
+        wgpu::ShaderSource::SpirV(words) => words.clone().into_owned(),
+        _ => unreachable!("wgpu::util::make_spirv always returns ShaderSource::SpirV"),
+    };
+
+    let reflection = ShaderReflection::from_spirv(&words);
+    let shader_name = path.as_ref().to_str();
+    let module = fw.gpu_device().create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: shader_name,
+        source,
+    });
+
+    Ok(Self { module, reflection })
+}
+
+/// Initialises a [`Shader`] from GLSL source for the given shader `stage`. Requires building
+/// with the `glsl` feature, which forwards to naga's `glsl-in`.
+#[cfg(feature = "glsl")]
+pub fn from_glsl(fw: &Framework, source: &str, stage: naga::ShaderStage) -> Self {
+    let reflection = ShaderReflection::from_glsl(source, stage);
+    let module = fw.gpu_device().create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Shader: from GLSL"),
+        source: wgpu::ShaderSource::Glsl {
+            shader: Cow::Borrowed(source),
+            stage,
+            defines: Default::default(),
         },
-    )))
-}}
\ No newline at end of file
+    });
+
+    Self { module, reflection }
+}
+}
\ No newline at end of file